@@ -1,7 +1,7 @@
 use crate::*;
 use near_sdk::borsh::{self};
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Bid {
     pub owner_id: AccountId,
@@ -37,6 +37,115 @@ pub struct PurchaseArgs {
     pub token_id: TokenId,
 }
 
+/// yoctoNEAR a storage deposit must cover for each active listing (NEP-145).
+const STORAGE_PER_SALE: u128 = 1_000 * near_sdk::env::STORAGE_PRICE_PER_BYTE;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+/// NEP-297 event standard/version emitted by this marketplace.
+pub const EVENT_STANDARD: &str = "nft_market";
+pub const EVENT_VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleCreatedLog {
+    pub nft_contract_id: AccountId,
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PriceUpdatedLog {
+    pub nft_contract_id: AccountId,
+    pub token_id: TokenId,
+    pub ft_token_id: FungibleTokenId,
+    pub price: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleRemovedLog {
+    pub nft_contract_id: AccountId,
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SalePurchasedLog {
+    pub nft_contract_id: AccountId,
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    pub buyer_id: AccountId,
+    pub ft_token_id: FungibleTokenId,
+    pub price: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PurchaseRefundedLog {
+    pub nft_contract_id: AccountId,
+    pub token_id: TokenId,
+    pub buyer_id: AccountId,
+    pub ft_token_id: FungibleTokenId,
+    pub price: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum EventLogVariant {
+    SaleCreated(Vec<SaleCreatedLog>),
+    PriceUpdated(Vec<PriceUpdatedLog>),
+    SaleRemoved(Vec<SaleRemovedLog>),
+    SalePurchased(Vec<SalePurchasedLog>),
+    PurchaseRefunded(Vec<PurchaseRefundedLog>),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventLog {
+    pub standard: String,
+    pub version: String,
+    #[serde(flatten)]
+    pub event: EventLogVariant,
+}
+
+impl EventLog {
+    pub fn new(event: EventLogVariant) -> Self {
+        Self {
+            standard: EVENT_STANDARD.to_string(),
+            version: EVENT_VERSION.to_string(),
+            event,
+        }
+    }
+
+    /// Logs the event prefixed with `EVENT_JSON:` per NEP-297.
+    pub fn emit(self) {
+        env::log(
+            format!(
+                "EVENT_JSON:{}",
+                near_sdk::serde_json::to_string(&self).unwrap()
+            )
+            .as_bytes(),
+        );
+    }
+}
+
 #[near_bindgen]
 impl Contract {
     #[payable]
@@ -48,12 +157,44 @@ impl Contract {
         approval_id: U64,
         sale_args: SaleArgs,
     ) {
+        self.assert_not_paused();
+        let owner: AccountId = owner_id.as_ref().clone();
+        let contract_id: AccountId = nft_contract_id.into();
         assert!(
-            self.storage_deposits.contains(owner_id.as_ref()),
-            "Must call storage_deposit with {} to sell on this market.",
-            STORAGE_AMOUNT
+            self.approved_nft_contract_ids.contains(&contract_id),
+            "{} is not an approved NFT contract",
+            contract_id
+        );
+        let contract_and_token_id = format!("{}:{}", contract_id, token_id);
+
+        // a secondary sale can re-list the same token under a new owner; clear the
+        // previous listing first so its escrowed bids are refunded and its owner's
+        // index entry isn't left stale
+        if let Some(existing) = self.sales.get(&contract_and_token_id) {
+            if existing.owner_id != owner {
+                self.refund_all_bids(&existing.bids);
+                self.internal_remove_index(&contract_and_token_id, &existing.owner_id, &contract_id);
+            }
+        }
+
+        // re-listing an existing token reuses its slot; a new token needs one more
+        let already_listed = self
+            .by_owner_id
+            .get(&owner)
+            .map_or(false, |by_owner| by_owner.contains(&contract_and_token_id));
+        let storage_paid = self.storage_deposits.get(&owner).unwrap_or(0);
+        let slots = if already_listed {
+            self.sales_by_owner_count(&owner)
+        } else {
+            self.sales_by_owner_count(&owner) + 1
+        };
+        let storage_required = slots * STORAGE_PER_SALE;
+        assert!(
+            storage_paid >= storage_required,
+            "Insufficient storage paid: {} required, {} deposited. Call storage_deposit.",
+            storage_required,
+            storage_paid
         );
-        let contract_id: AccountId = nft_contract_id.into();
 
         let SaleArgs {
             prices
@@ -84,19 +225,34 @@ impl Contract {
             }
         }
         
-        env::log(format!("add_sale for owner: {}", owner_id.as_ref()).as_bytes());
-
-        let bids = HashMap::new();
+        // preserve any escrowed bids when re-listing an existing token
+        let bids = if already_listed {
+            self.sales
+                .get(&contract_and_token_id)
+                .map(|sale| sale.bids)
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
 
         self.sales.insert(
-            &format!("{}:{}", contract_id, token_id),
+            &contract_and_token_id,
             &Sale {
-                owner_id: owner_id.into(),
+                owner_id: owner.clone(),
                 approval_id,
                 conditions,
                 bids,
             },
         );
+
+        self.internal_insert_index(&contract_and_token_id, &owner, &contract_id);
+
+        EventLog::new(EventLogVariant::SaleCreated(vec![SaleCreatedLog {
+            nft_contract_id: contract_id,
+            token_id,
+            owner_id: owner,
+        }]))
+        .emit();
     }
 
     pub fn update_price(
@@ -114,22 +270,145 @@ impl Contract {
             sale.owner_id,
             "Must be sale owner"
         );
-        sale.conditions.insert(ft_token_id.into(), price);
+        let ft_token_id: AccountId = ft_token_id.into();
+        sale.conditions.insert(ft_token_id.clone(), price);
         self.sales.insert(&contract_and_token_id, &sale);
+
+        EventLog::new(EventLogVariant::PriceUpdated(vec![PriceUpdatedLog {
+            nft_contract_id: contract_id,
+            token_id,
+            ft_token_id,
+            price,
+        }]))
+        .emit();
     }
 
     /// should be able to pull a sale without yocto redirect to wallet?
     pub fn remove_sale(&mut self, nft_contract_id: ValidAccountId, token_id: String) {
         let contract_id: AccountId = nft_contract_id.into();
+        let contract_and_token_id = format!("{}:{}", contract_id, token_id);
         let sale = self
             .sales
-            .remove(&format!("{}:{}", contract_id, token_id))
+            .remove(&contract_and_token_id)
             .expect("No sale");
         assert_eq!(
             env::predecessor_account_id(),
             sale.owner_id,
             "Must be sale owner"
         );
+        self.internal_remove_index(&contract_and_token_id, &sale.owner_id, &contract_id);
+        // refund every escrowed bidder now that the listing is gone
+        self.refund_all_bids(&sale.bids);
+
+        EventLog::new(EventLogVariant::SaleRemoved(vec![SaleRemovedLog {
+            nft_contract_id: contract_id,
+            token_id,
+            owner_id: sale.owner_id,
+        }]))
+        .emit();
+    }
+
+    #[payable]
+    pub fn add_bid(
+        &mut self,
+        nft_contract_id: ValidAccountId,
+        token_id: String,
+        ft_token_id: ValidAccountId,
+    ) {
+        let contract_id: AccountId = nft_contract_id.into();
+        let contract_and_token_id = format!("{}:{}", contract_id, token_id);
+        let sale = self.sales.get(&contract_and_token_id).expect("No sale");
+        let ft_token_id: AccountId = ft_token_id.into();
+        // attached deposit is NEAR, so only NEAR bids can be escrowed this way
+        assert_eq!(&ft_token_id, "near", "Attach NEAR to bid in NEAR");
+        // only a U128(0) condition is the "accepting bids" marker; fixed-price
+        // listings must be bought, not bid on
+        assert_eq!(
+            sale.conditions.get(&ft_token_id).map(|price| price.0),
+            Some(0),
+            "Sale does not accept bids in {}",
+            ft_token_id
+        );
+        let price = env::attached_deposit();
+        assert!(price > 0, "Attached deposit must be greater than 0");
+        self.internal_add_bid(
+            contract_and_token_id,
+            sale,
+            ft_token_id,
+            env::predecessor_account_id(),
+            U128(price),
+        );
+    }
+
+    /// Records a bid, enforcing that only a strictly higher bid displaces the
+    /// current one for the same `ft_token_id` and refunding the outbid bidder.
+    pub(crate) fn internal_add_bid(
+        &mut self,
+        contract_and_token_id: String,
+        mut sale: Sale,
+        ft_token_id: FungibleTokenId,
+        owner_id: AccountId,
+        price: U128,
+    ) {
+        if let Some(current_bid) = sale.bids.get(&ft_token_id) {
+            assert!(
+                price.0 > current_bid.price.0,
+                "Bid must be higher than current bid of {}",
+                current_bid.price.0
+            );
+            // refund the displaced bidder their escrowed funds
+            self.refund_bid(ft_token_id.clone(), &current_bid.clone());
+        }
+        sale.bids.insert(ft_token_id, Bid { owner_id, price });
+        self.sales.insert(&contract_and_token_id, &sale);
+    }
+
+    pub fn accept_bid(
+        &mut self,
+        nft_contract_id: ValidAccountId,
+        token_id: String,
+        ft_token_id: ValidAccountId,
+    ) -> Promise {
+        let contract_id: AccountId = nft_contract_id.into();
+        let contract_and_token_id = format!("{}:{}", contract_id, token_id);
+        let mut sale = self.sales.get(&contract_and_token_id).expect("No sale");
+        assert_eq!(
+            env::predecessor_account_id(),
+            sale.owner_id,
+            "Must be sale owner"
+        );
+        let ft_token_id: AccountId = ft_token_id.into();
+        let bid = sale.bids.remove(&ft_token_id).expect("No bid");
+        // every other escrowed bidder loses the token once the sale closes, so
+        // refund them before settling the accepted bid
+        let remaining_bids = std::mem::take(&mut sale.bids);
+        self.refund_all_bids(&remaining_bids);
+        // price the purchase at the escrowed bid amount, then settle it through
+        // the normal purchase path with the bidder as the buyer
+        sale.conditions.insert(ft_token_id.clone(), bid.price);
+        self.sales.insert(&contract_and_token_id, &sale);
+        self.process_purchase(contract_id, token_id, ft_token_id, bid.owner_id)
+    }
+
+    pub(crate) fn refund_bid(&mut self, ft_token_id: FungibleTokenId, bid: &Bid) {
+        if ft_token_id == "near" {
+            Promise::new(bid.owner_id.clone()).transfer(u128::from(bid.price));
+        } else {
+            ext_contract::ft_transfer(
+                bid.owner_id.clone(),
+                bid.price,
+                None,
+                &ft_token_id,
+                1,
+                GAS_FOR_FT_TRANSFER,
+            );
+        }
+    }
+
+    pub(crate) fn refund_all_bids(&mut self, bids: &HashMap<FungibleTokenId, Bid>) {
+        for (ft_token_id, bid) in bids {
+            self.refund_bid(ft_token_id.clone(), bid);
+        }
     }
 
     #[payable]
@@ -138,11 +417,14 @@ impl Contract {
         nft_contract_id: ValidAccountId,
         token_id: String,
     ) -> Promise {
+        self.assert_not_paused();
         let contract_id: AccountId = nft_contract_id.into();
         let contract_and_token_id = format!("{}:{}", contract_id, token_id);
         let sale = self.sales.get(&contract_and_token_id).expect("No sale");
         let near_token_id = "near".to_string();
         let price = sale.conditions.get(&near_token_id).expect("Not for sale in NEAR");
+        // a U128(0) condition is the "accepting bids" marker, not a free sale
+        assert!(price.0 > 0, "Not for direct sale in NEAR; place a bid instead");
         let deposit = env::attached_deposit();
         assert_eq!(
             env::attached_deposit(),
@@ -161,24 +443,34 @@ impl Contract {
         ft_token_id: AccountId,
         buyer_id: AccountId,
     ) -> Promise {
+        self.assert_not_paused();
         let contract_id: AccountId = nft_contract_id.into();
         let contract_and_token_id = format!("{}:{}", contract_id, token_id);
         let sale = self.sales.remove(&contract_and_token_id).expect("No sale");
+        self.internal_remove_index(&contract_and_token_id, &sale.owner_id, &contract_id);
+        // refund every bid still escrowed on the listing now that it closes
+        // (accept_bid has already taken the accepted bid out)
+        self.refund_all_bids(&sale.bids);
         let price = *sale.conditions.get(&ft_token_id).unwrap();
+        // royalties are computed over the amount left after the market fee
+        let payout_balance = U128(u128::from(price) - self.market_fee(price));
 
         ext_contract::nft_transfer(
             buyer_id.clone(),
             token_id.clone(),
             sale.owner_id.clone(),
             None,
-            price,
+            payout_balance,
             &contract_id,
             1,
             GAS_FOR_NFT_TRANSFER,
         )
         .then(ext_self::resolve_purchase(
+            contract_id,
+            token_id,
             ft_token_id,
             buyer_id,
+            payout_balance,
             sale,
             &env::current_account_id(),
             NO_DEPOSIT,
@@ -191,30 +483,42 @@ impl Contract {
     #[private]
     pub fn resolve_purchase(
         &mut self,
+        nft_contract_id: AccountId,
+        token_id: String,
         ft_token_id: AccountId,
         buyer_id: AccountId,
+        payout_balance: U128,
         sale: Sale,
     ) -> U128 {
 
         let price = *sale.conditions.get(&ft_token_id).unwrap();
+        // the fee was already skimmed in process_purchase; `payout_balance` is the
+        // net amount royalties must split, so the rate can't drift mid-promise
+        let net = u128::from(payout_balance);
+        let fee = u128::from(price) - net;
 
         // checking for payout information
         let payout_option = match env::promise_result(0) {
             PromiseResult::NotReady => unreachable!(),
             PromiseResult::Successful(value) => {
                 // None means a bad payout from bad NFT contract
-                near_sdk::serde_json::from_slice::<Payout>(&value).ok().and_then(|payout| {
-                    // gas to do 8 FT transfers (and definitely 8 NEAR transfers)
-                    if payout.len() > 8 {
+                near_sdk::serde_json::from_slice::<Payout>(&value).ok().and_then(|mut payout| {
+                    // tolerate rounding dust (e.g. 3333 + 3333 + 3333 vs 10000):
+                    // reject only over-payment or a gap wider than one yocto per
+                    // entry, then hand the remainder to the seller so the total
+                    // distributed equals the net amount exactly
+                    let sum: u128 = payout.values().map(|a| *a).sum();
+                    if sum > net || net - sum > payout.len() as u128 {
                         None
                     } else {
-                        // payouts must == sale.price, otherwise something wrong with NFT contract
-                        // TODO off by 1 e.g. payouts are fractions of 3333 + 3333 + 3333
-                        let sum: u128 = payout.values().map(|a| *a).reduce(|a, b| a + b).unwrap();
-                        if sum == u128::from(price) {
-                            Some(payout)
-                        } else {
+                        *payout.entry(sale.owner_id.clone()).or_insert(0) += net - sum;
+                        // gas to do 8 FT transfers (and definitely 8 NEAR transfers);
+                        // the treasury fee transfer claims one of those slots
+                        let fee_transfers = if fee > 0 { 1 } else { 0 };
+                        if payout.len() + fee_transfers > 8 {
                             None
+                        } else {
+                            Some(payout)
                         }
                     }
                 })
@@ -228,7 +532,14 @@ impl Contract {
         let payout = if let Some(payout_option) = payout_option {
             payout_option
         } else {
-            env::log(format!("Refunding {} to {}", u128::from(price), buyer_id).as_bytes());
+            EventLog::new(EventLogVariant::PurchaseRefunded(vec![PurchaseRefundedLog {
+                nft_contract_id,
+                token_id,
+                buyer_id: buyer_id.clone(),
+                ft_token_id: ft_token_id.clone(),
+                price,
+            }]))
+            .emit();
             // refund NEAR
             if ft_token_id == "near" {
                 Promise::new(buyer_id).transfer(u128::from(price));
@@ -239,6 +550,32 @@ impl Contract {
 
         env::log(format!("Royalty {:?}", payout).as_bytes());
 
+        EventLog::new(EventLogVariant::SalePurchased(vec![SalePurchasedLog {
+            nft_contract_id,
+            token_id,
+            owner_id: sale.owner_id.clone(),
+            buyer_id: buyer_id.clone(),
+            ft_token_id: ft_token_id.clone(),
+            price,
+        }]))
+        .emit();
+
+        // route the market fee to the treasury before paying royalties
+        if fee > 0 {
+            if ft_token_id == "near" {
+                Promise::new(self.treasury_id.clone()).transfer(fee);
+            } else {
+                ext_contract::ft_transfer(
+                    self.treasury_id.clone(),
+                    U128(fee),
+                    None,
+                    &ft_token_id,
+                    1,
+                    GAS_FOR_FT_TRANSFER,
+                );
+            }
+        }
+
         // NEAR payouts
         if ft_token_id == "near" {
             for (receiver_id, amount) in &payout {
@@ -265,6 +602,195 @@ impl Contract {
         }
     }                             
 
+    /// Register or top up a storage deposit (NEP-145). Only whole listing slots
+    /// are credited; any remainder is refunded to the predecessor.
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<ValidAccountId>) -> StorageBalance {
+        let storage_account_id = account_id
+            .map(|a| a.into())
+            .unwrap_or_else(env::predecessor_account_id);
+        let deposit = env::attached_deposit();
+        assert!(
+            deposit >= STORAGE_PER_SALE,
+            "Requires minimum deposit of {}",
+            STORAGE_PER_SALE
+        );
+        let credit = deposit - (deposit % STORAGE_PER_SALE);
+        let mut balance = self.storage_deposits.get(&storage_account_id).unwrap_or(0);
+        balance += credit;
+        self.storage_deposits.insert(&storage_account_id, &balance);
+        // refund the remainder that doesn't fill a whole slot
+        if deposit > credit {
+            Promise::new(env::predecessor_account_id()).transfer(deposit - credit);
+        }
+        let locked = self.sales_by_owner_count(&storage_account_id) * STORAGE_PER_SALE;
+        StorageBalance {
+            total: U128(balance),
+            available: U128(balance - locked),
+        }
+    }
+
+    /// Reclaim storage not locked by an active listing (NEP-145).
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        near_sdk::assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let mut balance = self.storage_deposits.get(&owner_id).unwrap_or(0);
+        let locked = self.sales_by_owner_count(&owner_id) * STORAGE_PER_SALE;
+        let available = balance - locked;
+        let to_withdraw = amount.map(u128::from).unwrap_or(available);
+        assert!(
+            to_withdraw <= available,
+            "Not enough available balance to withdraw {}",
+            to_withdraw
+        );
+        balance -= to_withdraw;
+        self.storage_deposits.insert(&owner_id, &balance);
+        Promise::new(owner_id).transfer(to_withdraw);
+        StorageBalance {
+            total: U128(balance),
+            available: U128(balance - locked),
+        }
+    }
+
+    pub fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        let account_id: AccountId = account_id.into();
+        self.storage_deposits.get(&account_id).map(|balance| {
+            let locked = self.sales_by_owner_count(&account_id) * STORAGE_PER_SALE;
+            StorageBalance {
+                total: U128(balance),
+                available: U128(balance - locked),
+            }
+        })
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(STORAGE_PER_SALE),
+            max: None,
+        }
+    }
+
+    /// Number of active listings owned by `owner_id`, used to compute locked
+    /// storage.
+    pub(crate) fn sales_by_owner_count(&self, owner_id: &AccountId) -> u128 {
+        self.by_owner_id
+            .get(owner_id)
+            .map(|by_owner| by_owner.len() as u128)
+            .unwrap_or(0)
+    }
+
+    /// Keep the per-owner and per-contract indexes in sync with a new listing.
+    pub(crate) fn internal_insert_index(
+        &mut self,
+        contract_and_token_id: &String,
+        owner_id: &AccountId,
+        nft_contract_id: &AccountId,
+    ) {
+        let mut by_owner_id = self.by_owner_id.get(owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::ByOwnerIdInner {
+                    account_id_hash: hash_account_id(owner_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+        by_owner_id.insert(contract_and_token_id);
+        self.by_owner_id.insert(owner_id, &by_owner_id);
+
+        let mut by_nft_contract_id = self
+            .by_nft_contract_id
+            .get(nft_contract_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(
+                    StorageKey::ByNFTContractIdInner {
+                        account_id_hash: hash_account_id(nft_contract_id),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
+            });
+        by_nft_contract_id.insert(contract_and_token_id);
+        self.by_nft_contract_id
+            .insert(nft_contract_id, &by_nft_contract_id);
+    }
+
+    /// Drop a listing from the per-owner and per-contract indexes.
+    pub(crate) fn internal_remove_index(
+        &mut self,
+        contract_and_token_id: &String,
+        owner_id: &AccountId,
+        nft_contract_id: &AccountId,
+    ) {
+        if let Some(mut by_owner_id) = self.by_owner_id.get(owner_id) {
+            by_owner_id.remove(contract_and_token_id);
+            if by_owner_id.is_empty() {
+                self.by_owner_id.remove(owner_id);
+            } else {
+                self.by_owner_id.insert(owner_id, &by_owner_id);
+            }
+        }
+        if let Some(mut by_nft_contract_id) = self.by_nft_contract_id.get(nft_contract_id) {
+            by_nft_contract_id.remove(contract_and_token_id);
+            if by_nft_contract_id.is_empty() {
+                self.by_nft_contract_id.remove(nft_contract_id);
+            } else {
+                self.by_nft_contract_id
+                    .insert(nft_contract_id, &by_nft_contract_id);
+            }
+        }
+    }
+
+    pub fn get_supply_sales(&self) -> U64 {
+        U64(self.sales.len())
+    }
+
+    pub fn get_sales(&self, from_index: U64, limit: U64) -> Vec<Sale> {
+        let keys = self.sales.keys_as_vector();
+        let from_index = u64::from(from_index);
+        let limit = u64::from(limit);
+        (from_index..std::cmp::min(from_index.saturating_add(limit), keys.len()))
+            .map(|index| self.sales.get(&keys.get(index).unwrap()).unwrap())
+            .collect()
+    }
+
+    pub fn get_sales_by_owner_id(
+        &self,
+        account_id: AccountId,
+        from_index: U64,
+        limit: U64,
+    ) -> Vec<Sale> {
+        let by_owner_id = match self.by_owner_id.get(&account_id) {
+            Some(by_owner_id) => by_owner_id,
+            None => return vec![],
+        };
+        let keys = by_owner_id.as_vector();
+        let from_index = u64::from(from_index);
+        let limit = u64::from(limit);
+        (from_index..std::cmp::min(from_index.saturating_add(limit), keys.len()))
+            .map(|index| self.sales.get(&keys.get(index).unwrap()).unwrap())
+            .collect()
+    }
+
+    pub fn get_sales_by_nft_contract_id(
+        &self,
+        nft_contract_id: AccountId,
+        from_index: U64,
+        limit: U64,
+    ) -> Vec<Sale> {
+        let by_nft_contract_id = match self.by_nft_contract_id.get(&nft_contract_id) {
+            Some(by_nft_contract_id) => by_nft_contract_id,
+            None => return vec![],
+        };
+        let keys = by_nft_contract_id.as_vector();
+        let from_index = u64::from(from_index);
+        let limit = u64::from(limit);
+        (from_index..std::cmp::min(from_index.saturating_add(limit), keys.len()))
+            .map(|index| self.sales.get(&keys.get(index).unwrap()).unwrap())
+            .collect()
+    }
+
     pub fn get_sale(&self, nft_contract_id: ValidAccountId, token_id: String) -> Sale {
         let contract_id: AccountId = nft_contract_id.into();
         self.sales
@@ -273,14 +799,147 @@ impl Contract {
     }
 }
 
+#[near_bindgen]
+impl Contract {
+    pub fn set_owner(&mut self, owner_id: ValidAccountId) {
+        self.assert_owner();
+        self.owner_id = owner_id.into();
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    pub fn pause(&mut self) {
+        self.assert_owner();
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_market_fee(&mut self, fee_bps: u16) {
+        self.assert_owner();
+        assert!(fee_bps <= 10_000, "fee_bps must be <= 10000");
+        self.fee_bps = fee_bps;
+    }
+
+    pub fn get_market_fee(&self) -> u16 {
+        self.fee_bps
+    }
+
+    pub fn set_treasury(&mut self, treasury_id: ValidAccountId) {
+        self.assert_owner();
+        self.treasury_id = treasury_id.into();
+    }
+
+    pub fn get_treasury(&self) -> AccountId {
+        self.treasury_id.clone()
+    }
+
+    pub fn add_approved_nft_contract_id(&mut self, nft_contract_id: ValidAccountId) {
+        self.assert_owner();
+        self.approved_nft_contract_ids.insert(nft_contract_id.as_ref());
+    }
+
+    pub fn remove_approved_nft_contract_id(&mut self, nft_contract_id: ValidAccountId) {
+        self.assert_owner();
+        self.approved_nft_contract_ids.remove(nft_contract_id.as_ref());
+    }
+
+    pub fn is_approved_nft_contract_id(&self, nft_contract_id: ValidAccountId) -> bool {
+        self.approved_nft_contract_ids.contains(nft_contract_id.as_ref())
+    }
+
+    pub(crate) fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner_id,
+            "Only the marketplace owner can call this method"
+        );
+    }
+
+    pub(crate) fn assert_not_paused(&self) {
+        assert!(!self.paused, "The marketplace is paused");
+    }
+
+    /// The market fee owed on a `price`, in the same token as `price`.
+    pub(crate) fn market_fee(&self, price: U128) -> u128 {
+        u128::from(price) * self.fee_bps as u128 / 10_000
+    }
+}
+
+/// Receive FT payments for FT-denominated sales (NEP-141).
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+    /// `msg` is a JSON-encoded `PurchaseArgs`; the calling FT contract is read
+    /// from `predecessor_account_id` and used as the `ft_token_id`.
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let PurchaseArgs {
+            nft_contract_id,
+            token_id,
+        } = near_sdk::serde_json::from_str(&msg).expect("Not valid PurchaseArgs");
+        let contract_id: AccountId = nft_contract_id.into();
+        let contract_and_token_id = format!("{}:{}", contract_id, token_id);
+        let sale = self.sales.get(&contract_and_token_id).expect("No sale");
+        let ft_token_id = env::predecessor_account_id();
+        let price = *sale
+            .conditions
+            .get(&ft_token_id)
+            .expect("Not for sale in this FT");
+        if price.0 == 0 {
+            // "accepting bids" marker: escrow the incoming FT as a bid. The funds
+            // are consumed (held by the market) until the bid is accepted, which
+            // pays them out through process_purchase, or until it is outbid or the
+            // listing closes without it, at which point refund_bid returns them.
+            self.internal_add_bid(
+                contract_and_token_id,
+                sale,
+                ft_token_id,
+                sender_id.into(),
+                amount,
+            );
+            return PromiseOrValue::Value(U128(0));
+        }
+        assert_eq!(
+            amount.0,
+            u128::from(price),
+            "Must pay exactly the sale amount {}",
+            u128::from(price)
+        );
+        // settle through the normal purchase path; resolve_purchase returns any
+        // FTs to refund, otherwise U128(0) when the funds are consumed
+        PromiseOrValue::Promise(self.process_purchase(
+            contract_id,
+            token_id,
+            ft_token_id,
+            sender_id.into(),
+        ))
+    }
+}
+
 /// self call
 
 #[ext_contract(ext_self)]
 trait ExtSelf {
     fn resolve_purchase(
         &mut self,
+        nft_contract_id: AccountId,
+        token_id: String,
         ft_token_id: AccountId,
         buyer_id: AccountId,
+        payout_balance: U128,
         sale: Sale,
     ) -> Promise;
 }